@@ -1,29 +1,49 @@
-use std::str::MaybeOwned;
+use std::borrow::{Cow, IntoCow};
 
 use traits::Set;
 use {Default, Display, Script, Title};
 
 #[deriving(Clone)]
 pub struct Properties {
-    boxed: bool,
+    boxed: BoxStyle,
+    fill: Option<String>,
+    height: Option<f64>,
     hidden: bool,
+    invert: Option<Invert>,
     justification: Option<Justification>,
+    maxcols: Option<MaxCols>,
+    maxrows: Option<MaxRows>,
+    opaque: bool,
     order: Option<Order>,
     position: Option<Position>,
+    reverse: Option<Reverse>,
+    samplen: Option<f64>,
+    spacing: Option<f64>,
     stacked: Option<Stacked>,
-    title: Option<MaybeOwned<'static>>,
+    title: Option<TitleStyle>,
+    width: Option<f64>,
 }
 
 impl Default for Properties {
     fn default() -> Properties {
         Properties {
-            boxed: false,
+            boxed: BoxStyle::default(),
+            fill: None,
+            height: None,
             hidden: false,
+            invert: None,
             justification: None,
+            maxcols: None,
+            maxrows: None,
+            opaque: false,
             order: None,
             position: None,
+            reverse: None,
+            samplen: None,
+            spacing: None,
             stacked: None,
             title: None,
+            width: None,
         }
     }
 }
@@ -42,6 +62,18 @@ impl Properties {
         self.hidden = false;
         self
     }
+
+    fn title_mut(&mut self) -> &mut TitleStyle {
+        if self.title.is_none() {
+            self.title = Some(TitleStyle {
+                text: "".into_cow(),
+                font: None,
+                textcolor: None,
+            });
+        }
+
+        self.title.as_mut().unwrap()
+    }
 }
 
 impl Script for Properties {
@@ -60,6 +92,13 @@ impl Script for Properties {
             Some(Position::Outside(v, h)) => {
                 script.push_str(format!("outside {} {} ", v.display(), h.display())[])
             },
+            Some(Position::At { system, x, y, anchor }) => {
+                script.push_str(format!("at {} {}, {} ", system.display(), x, y)[]);
+
+                if let Some((v, h)) = anchor {
+                    script.push_str(format!("{} {} ", v.display(), h.display())[]);
+                }
+            },
         }
 
         if let Some(stacked) =  self.stacked {
@@ -67,6 +106,14 @@ impl Script for Properties {
             script.push(' ');
         }
 
+        if let Some(maxcols) = self.maxcols {
+            script.push_str(format!("maxcols {} ", maxcols.into_u32())[]);
+        }
+
+        if let Some(maxrows) = self.maxrows {
+            script.push_str(format!("maxrows {} ", maxrows.into_u32())[]);
+        }
+
         if let Some(justification) = self.justification {
             script.push_str(justification.display());
             script.push(' ');
@@ -77,12 +124,66 @@ impl Script for Properties {
             script.push(' ');
         }
 
+        if let Some(reverse) = self.reverse {
+            script.push_str(reverse.display());
+            script.push(' ');
+        }
+
+        if let Some(invert) = self.invert {
+            script.push_str(invert.display());
+            script.push(' ');
+        }
+
         if let Some(ref title) = self.title {
-            script.push_str(format!("title '{}' ", title)[])
+            script.push_str(format!("title '{}' ", title.text)[]);
+
+            if let Some((ref name, size)) = title.font {
+                script.push_str(format!("font '{},{}' ", name, size)[]);
+            }
+
+            if let Some(ref textcolor) = title.textcolor {
+                script.push_str(format!("textcolor rgb '{}' ", textcolor)[]);
+            }
         }
 
-        if self.boxed {
-            script.push_str("box ")
+        if let Some(spacing) = self.spacing {
+            script.push_str(format!("spacing {} ", spacing)[]);
+        }
+
+        if let Some(samplen) = self.samplen {
+            script.push_str(format!("samplen {} ", samplen)[]);
+        }
+
+        if let Some(width) = self.width {
+            script.push_str(format!("width {} ", width)[]);
+        }
+
+        if let Some(height) = self.height {
+            script.push_str(format!("height {} ", height)[]);
+        }
+
+        if self.boxed.enabled {
+            script.push_str("box ");
+
+            if let Some(lt) = self.boxed.line_type {
+                script.push_str(format!("linetype {} ", lt)[]);
+            }
+
+            if let Some(lw) = self.boxed.line_width {
+                script.push_str(format!("linewidth {} ", lw)[]);
+            }
+
+            if let Some(ref color) = self.boxed.color {
+                script.push_str(format!("linecolor rgb '{}' ", color)[]);
+            }
+        }
+
+        if self.opaque {
+            script.push_str("opaque ");
+
+            if let Some(ref fill) = self.fill {
+                script.push_str(format!("fillcolor rgb '{}' ", fill)[]);
+            }
         }
 
         script.push('\n');
@@ -96,14 +197,79 @@ impl Set<Boxed> for Properties {
     /// **Note** The key is not boxed by default
     fn set(&mut self, boxed: Boxed) -> &mut Properties {
         match boxed {
-            Boxed::No => self.boxed = false,
-            Boxed::Yes => self.boxed = true,
+            Boxed::No => self.boxed.enabled = false,
+            Boxed::Yes => self.boxed.enabled = true,
         }
 
         self
     }
 }
 
+impl Set<BoxColor> for Properties {
+    /// Changes the color of the box that surrounds the key
+    fn set(&mut self, color: BoxColor) -> &mut Properties {
+        self.boxed.color = Some(color.0);
+        self
+    }
+}
+
+impl Set<BoxLineType> for Properties {
+    /// Changes the line type of the box that surrounds the key
+    fn set(&mut self, lt: BoxLineType) -> &mut Properties {
+        self.boxed.line_type = Some(lt.0);
+        self
+    }
+}
+
+impl Set<BoxLineWidth> for Properties {
+    /// Changes the line width of the box that surrounds the key
+    fn set(&mut self, lw: BoxLineWidth) -> &mut Properties {
+        self.boxed.line_width = Some(lw.0);
+        self
+    }
+}
+
+impl Set<FillColor> for Properties {
+    /// Changes the color used to fill the key when it's `Opaque::Yes`
+    fn set(&mut self, fill: FillColor) -> &mut Properties {
+        self.fill = Some(fill.0);
+        self
+    }
+}
+
+impl Set<Height> for Properties {
+    /// Changes the height of the key box by this increment to its default height
+    fn set(&mut self, height: Height) -> &mut Properties {
+        self.height = Some(height.0);
+        self
+    }
+}
+
+impl Set<Opaque> for Properties {
+    /// Select if the key gets an opaque background, so it does not blend with plotted lines
+    ///
+    /// **Note** The key is not opaque by default
+    fn set(&mut self, opaque: Opaque) -> &mut Properties {
+        match opaque {
+            Opaque::No => self.opaque = false,
+            Opaque::Yes => self.opaque = true,
+        }
+
+        self
+    }
+}
+
+impl Set<Invert> for Properties {
+    /// Reverses the top-to-bottom order of the entries, so the last plotted series is listed
+    /// first
+    ///
+    /// **Note** The entries are not inverted by default
+    fn set(&mut self, invert: Invert) -> &mut Properties {
+        self.invert = Some(invert);
+        self
+    }
+}
+
 impl Set<Justification> for Properties {
     /// Changes the justification of the text of each entry
     ///
@@ -114,6 +280,26 @@ impl Set<Justification> for Properties {
     }
 }
 
+impl Set<MaxCols> for Properties {
+    /// Changes the maximum number of columns the key entries are stacked into
+    ///
+    /// **Note** `MaxCols::Auto` lets gnuplot pick the number of columns
+    fn set(&mut self, maxcols: MaxCols) -> &mut Properties {
+        self.maxcols = Some(maxcols);
+        self
+    }
+}
+
+impl Set<MaxRows> for Properties {
+    /// Changes the maximum number of rows the key entries are stacked into
+    ///
+    /// **Note** `MaxRows::Auto` lets gnuplot pick the number of rows
+    fn set(&mut self, maxrows: MaxRows) -> &mut Properties {
+        self.maxrows = Some(maxrows);
+        self
+    }
+}
+
 impl Set<Order> for Properties {
     /// How to order each entry
     ///
@@ -134,6 +320,32 @@ impl Set<Position> for Properties {
     }
 }
 
+impl Set<Reverse> for Properties {
+    /// Swaps which side of the label the line/point sample is drawn on
+    ///
+    /// **Note** The samples are not reversed by default
+    fn set(&mut self, reverse: Reverse) -> &mut Properties {
+        self.reverse = Some(reverse);
+        self
+    }
+}
+
+impl Set<SampleLen> for Properties {
+    /// Changes the length of the sample line
+    fn set(&mut self, samplen: SampleLen) -> &mut Properties {
+        self.samplen = Some(samplen.0);
+        self
+    }
+}
+
+impl Set<Spacing> for Properties {
+    /// Changes the vertical spacing between two consecutive entries
+    fn set(&mut self, spacing: Spacing) -> &mut Properties {
+        self.spacing = Some(spacing.0);
+        self
+    }
+}
+
 impl Set<Stacked> for Properties {
     /// Changes how the entries of the key are stacked
     fn set(&mut self, stacked: Stacked) -> &mut Properties {
@@ -142,9 +354,33 @@ impl Set<Stacked> for Properties {
     }
 }
 
-impl<S> Set<Title<S>> for Properties where S: IntoMaybeOwned<'static> {
+impl<S> Set<Title<S>> for Properties where S: IntoCow<'static, str> {
     fn set(&mut self, title: Title<S>) -> &mut Properties {
-        self.title = Some(title.0.into_maybe_owned());
+        self.title_mut().text = title.0.into_cow();
+        self
+    }
+}
+
+impl Set<TitleColor> for Properties {
+    /// Changes the color used to render the title of the key
+    fn set(&mut self, color: TitleColor) -> &mut Properties {
+        self.title_mut().textcolor = Some(color.0);
+        self
+    }
+}
+
+impl Set<TitleFont> for Properties {
+    /// Changes the font used to render the title of the key
+    fn set(&mut self, font: TitleFont) -> &mut Properties {
+        self.title_mut().font = Some((font.0, font.1));
+        self
+    }
+}
+
+impl Set<Width> for Properties {
+    /// Changes the width of the key box by this increment to its default width
+    fn set(&mut self, width: Width) -> &mut Properties {
+        self.width = Some(width.0);
         self
     }
 }
@@ -155,6 +391,41 @@ pub enum Boxed {
     Yes,
 }
 
+/// Style of the box that surrounds the key, see `Boxed`
+#[deriving(Clone)]
+struct BoxStyle {
+    color: Option<String>,
+    enabled: bool,
+    line_type: Option<i32>,
+    line_width: Option<f64>,
+}
+
+impl Default for BoxStyle {
+    fn default() -> BoxStyle {
+        BoxStyle {
+            color: None,
+            enabled: false,
+            line_type: None,
+            line_width: None,
+        }
+    }
+}
+
+/// Color of the box that surrounds the key, see `Boxed`
+pub struct BoxColor(pub String);
+
+/// Line type of the box that surrounds the key, see `Boxed`
+pub struct BoxLineType(pub i32);
+
+/// Line width of the box that surrounds the key, see `Boxed`
+pub struct BoxLineWidth(pub f64);
+
+/// Color used to fill the key when it's opaque, see `Opaque`
+pub struct FillColor(pub String);
+
+/// Height of the key box, as an increment of the default height, in characters
+pub struct Height(pub f64);
+
 /// Horizontal position of the key
 #[deriving(Clone)]
 pub enum Horizontal {
@@ -163,6 +434,22 @@ pub enum Horizontal {
     Right,
 }
 
+/// Whether to invert the top-to-bottom order of the entries of the key
+#[deriving(Clone)]
+pub enum Invert {
+    No,
+    Yes,
+}
+
+impl Display for Invert {
+    fn display(&self) -> &'static str {
+        match *self {
+            Invert::No => "noinvert",
+            Invert::Yes => "invert",
+        }
+    }
+}
+
 /// Text justification of the key
 #[deriving(Clone)]
 pub enum Justification {
@@ -170,6 +457,48 @@ pub enum Justification {
     Right,
 }
 
+/// Maximum number of columns the key entries are stacked into
+#[deriving(Clone)]
+pub enum MaxCols {
+    /// Let gnuplot choose the number of columns
+    Auto,
+    /// Use exactly this many columns
+    Columns(u32),
+}
+
+impl MaxCols {
+    fn into_u32(self) -> u32 {
+        match self {
+            MaxCols::Auto => 0,
+            MaxCols::Columns(n) => n,
+        }
+    }
+}
+
+/// Maximum number of rows the key entries are stacked into
+#[deriving(Clone)]
+pub enum MaxRows {
+    /// Let gnuplot choose the number of rows
+    Auto,
+    /// Use exactly this many rows
+    Rows(u32),
+}
+
+impl MaxRows {
+    fn into_u32(self) -> u32 {
+        match self {
+            MaxRows::Auto => 0,
+            MaxRows::Rows(n) => n,
+        }
+    }
+}
+
+/// Whether the key has an opaque background or not
+pub enum Opaque {
+    No,
+    Yes,
+}
+
 /// Order of the elements of the key
 #[deriving(Clone)]
 pub enum Order {
@@ -178,13 +507,68 @@ pub enum Order {
 }
 
 /// Position of the key
-// TODO XY position
 #[deriving(Clone)]
 pub enum Position {
     Inside(Vertical, Horizontal),
     Outside(Vertical, Horizontal),
+    /// Pins the key at an explicit coordinate, optionally anchored like `Inside`/`Outside`
+    At {
+        system: CoordinateSystem,
+        x: f64,
+        y: f64,
+        anchor: Option<(Vertical, Horizontal)>,
+    },
+}
+
+/// Coordinate system used to interpret the `x`/`y` pair of `Position::At`
+#[deriving(Clone)]
+pub enum CoordinateSystem {
+    /// Data coordinates of the first (bottom/left) axes
+    First,
+    /// Data coordinates of the second (top/right) axes
+    Second,
+    /// Fraction (0..1) of the plot area
+    Graph,
+    /// Fraction (0..1) of the whole canvas
+    Screen,
+    /// Character units
+    Character,
+}
+
+impl Display for CoordinateSystem {
+    fn display(&self) -> &'static str {
+        match *self {
+            CoordinateSystem::First => "first",
+            CoordinateSystem::Second => "second",
+            CoordinateSystem::Graph => "graph",
+            CoordinateSystem::Screen => "screen",
+            CoordinateSystem::Character => "character",
+        }
+    }
+}
+
+/// Whether to swap which side of the label the line/point sample is drawn on
+#[deriving(Clone)]
+pub enum Reverse {
+    No,
+    Yes,
+}
+
+impl Display for Reverse {
+    fn display(&self) -> &'static str {
+        match *self {
+            Reverse::No => "noreverse",
+            Reverse::Yes => "reverse",
+        }
+    }
 }
 
+/// Length of the sample line drawn next to each entry
+pub struct SampleLen(pub f64);
+
+/// Vertical spacing between two consecutive entries, as a multiple of the default spacing
+pub struct Spacing(pub f64);
+
 /// How the entries of the key are stacked
 #[deriving(Clone)]
 pub enum Stacked {
@@ -192,6 +576,20 @@ pub enum Stacked {
     Vertically,
 }
 
+/// Style of the title of the key, see `Title`
+#[deriving(Clone)]
+struct TitleStyle {
+    text: Cow<'static, str>,
+    font: Option<(String, f64)>,
+    textcolor: Option<String>,
+}
+
+/// Color used to render the title of the key, see `Title`
+pub struct TitleColor(pub String);
+
+/// Font, and its size in points, used to render the title of the key, see `Title`
+pub struct TitleFont(pub String, pub f64);
+
 /// Vertical position of the key
 #[deriving(Clone)]
 pub enum Vertical{
@@ -199,3 +597,6 @@ pub enum Vertical{
     Center,
     Top,
 }
+
+/// Width of the key box, as an increment of the default width, in characters
+pub struct Width(pub f64);